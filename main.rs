@@ -1,73 +1,126 @@
 // Externe Bibliotheken
-use rand::Rng;                                   // Für Zufallszahlengenerierung
-use rayon::prelude::*;                           // Für parallele Berechnung
+use pi_approximieren::{approximiere_pi, SamplingModus}; // Kernlogik der Monte-Carlo-Simulation
 use std::time::Instant;                          // Für Zeitmessung
 use std::io::{self, Write};                      // Für Ein-/Ausgabe-Operationen
-use std::sync::atomic::{AtomicU64, Ordering};    // Für thread-sichere Zähler
 use std::sync::Arc;                              // Für thread-sicheres Reference Counting
 
 // Konfigurationskonstanten
 const MIN_TROPFEN: u64 = 1000;           // Minimale Anzahl von Punkten für aussagekräftige Ergebnisse
 const DEFAULT_TROPFEN: u64 = 1_000_000;  // Standardwert für Punktanzahl
-const CHUNK_SIZE: u64 = 10_000;          // Minimale Chunk-Größe für parallele Verarbeitung
+
+// Stufen für den Konvergenz-Sweep: 10³ bis 10⁸ Tropfen.
+const SWEEP_STUFEN: [u64; 6] = [1_000, 10_000, 100_000, 1_000_000, 10_000_000, 100_000_000];
+
+// z-Wert für das 95%-Konfidenzintervall der Normalverteilung.
+const KONFIDENZ_Z_95: f64 = 1.96;
 
 // Struktur zur Speicherung der Berechnungsergebnisse
 #[derive(Debug)]
 struct Ergebnis {
-    pi_approx: f64,              // Angenäherter Pi Wert
-    dauer: std::time::Duration,  // Berechnungsdauer
-    tropfenzahl: u64,            // Anzahl verwendeter Punkte
-    threads: usize,              // Anzahl verwendeter Threads
+    pi_approx: f64,                      // Angenäherter Pi Wert
+    dauer: std::time::Duration,          // Berechnungsdauer
+    tropfenzahl: u64,                    // Anzahl verwendeter Punkte
+    threads: usize,                      // Anzahl verwendeter Threads
+    std_fehler: Option<f64>,             // Standardfehler der π-Schätzung (nur bei i.i.d. Sampling)
+    konfidenz_intervall: Option<(f64, f64)>, // 95%-Konfidenzintervall für π (nur bei i.i.d. Sampling)
 }
 
-// Hauptfunktion zur Pi-annäherung mit der Monte-Carlo Methode
-// Funktion:
-// - Verhältnis der Fläche eines Viertelkreises zur Fläche eines Quadranten
-// - A_Kreis / A_Quadrat = π/4
-// - Daraus folgt: π ≈ 4 * (Punkte im Kreis / Gesamtpunkte)
-fn approximiere_pi(tropfenzahl: u64) -> f64 {
-    let threads = rayon::current_num_threads();  // Ermittle verfügbare Threads
-    let counter = Arc::new(AtomicU64::new(0));   // Thread-sicherer Zähler für Treffer im Kreis
-    
-    // Berechne optimale Chunk Größe für Load Balancing
-    // - Mindestens CHUNK_SIZE (10.000)
-    // - Maximal tropfenzahl
-    // - Ziel: ca. 10 Chunks pro Thread
-    let chunk_size = (tropfenzahl / (threads as u64 * 10))
-        .max(CHUNK_SIZE)
-        .min(tropfenzahl);
-    
-    // Erstelle Vektor mit Start Indizes für jeden Chunk
-    let chunks: Vec<u64> = (0..tropfenzahl)
-        .step_by(chunk_size as usize)
+impl Ergebnis {
+    // Absoluter Fehler gegenüber dem tatsächlichen Wert von π.
+    fn abweichung(&self) -> f64 {
+        (self.pi_approx - std::f64::consts::PI).abs()
+    }
+}
+
+// Berechnet den Standardfehler und das 95%-Konfidenzintervall der π-Schätzung
+// aus der Binomialverteilung der Trefferquote p = treffer/N:
+// Var(p) = p(1-p)/N, und weil π = 4p ist SE(π) = 4·√(p(1-p)/N).
+// Das ist ohne Kenntnis des echten π berechenbar und spiegelt die tatsächliche
+// Unsicherheit der Monte-Carlo-Schätzung wider.
+//
+// Beide Größen setzen i.i.d.-Zufallsstichproben voraus und sind daher nur im
+// Modus `Pseudozufall` aussagekräftig: `Halton` samplet deterministisch ohne
+// Stichprobenvarianz, weshalb hier `None` zurückgegeben wird.
+fn berechne_konfidenz(
+    pi_approx: f64,
+    tropfenzahl: u64,
+    modus: SamplingModus,
+) -> (Option<f64>, Option<(f64, f64)>) {
+    if modus != SamplingModus::Pseudozufall {
+        return (None, None);
+    }
+
+    let p = pi_approx / 4.0;
+    let n = tropfenzahl as f64;
+    let std_fehler = 4.0 * (p * (1.0 - p) / n).sqrt();
+    let untergrenze = pi_approx - KONFIDENZ_Z_95 * std_fehler;
+    let obergrenze = pi_approx + KONFIDENZ_Z_95 * std_fehler;
+    (Some(std_fehler), Some((untergrenze, obergrenze)))
+}
+
+// Ermittelt die Hardware-Topologie (NUMA-Knoten, physische Kerne) und baut einen
+// Rayon-ThreadPool, dessen Worker beim Start jeweils an einen physischen Kern
+// gepinnt werden. Das verhindert, dass Rayon-Worker zwischen Kernen migrieren,
+// was auf Mehrsockel-/NUMA-Maschinen die Skalierung destabilisiert.
+//
+// Benötigt hwlocality 1.0.0-alpha.* (aktuell die einzige auflösbare 1.x-Version)
+// sowie die native hwloc-Systembibliothek zum Bauen. Das Pinning selbst ist
+// opt-in (Abfrage in `main`); bei Problemen zur Laufzeit wird auf den globalen
+// Rayon-Pool zurückgefallen statt zu paniken.
+fn erstelle_numa_threadpool() -> Option<rayon::ThreadPool> {
+    use hwlocality::cpu::binding::CpuBindingFlags;
+    use hwlocality::object::types::ObjectType;
+    use hwlocality::Topology;
+
+    let topologie = match Topology::new() {
+        Ok(topologie) => Arc::new(topologie),
+        Err(e) => {
+            eprintln!("NUMA-Pinning deaktiviert: Hardware-Topologie konnte nicht ermittelt werden ({}).", e);
+            return None;
+        }
+    };
+    let kerne: Vec<_> = topologie.objects_with_type(ObjectType::Core).collect();
+    let numa_knoten = topologie.objects_with_type(ObjectType::NUMANode).count().max(1);
+
+    println!(
+        "Erkannte Topologie: {} NUMA-Knoten, {} Kerne ({} Kerne/Knoten)",
+        numa_knoten,
+        kerne.len(),
+        kerne.len() / numa_knoten
+    );
+
+    // Cpusets einmalig einsammeln (statt bei jedem Worker-Start erneut die
+    // komplette Kernliste zu durchsuchen) und als eigenständige Werte halten,
+    // damit der start_handler sie ohne erneute Kernsuche direkt verwenden kann.
+    let kern_cpusets: Vec<_> = kerne
+        .iter()
+        .filter_map(|kern| kern.cpuset().map(|cpuset| cpuset.to_owned()))
         .collect();
-    
-    // Parallele Verarbeitung der Chunks
-    chunks.par_iter()  // Parallelisierung mittels Rayon
-        .for_each(|&start| {
-            let mut rng = rand::thread_rng();  // Thread lokaler Zufallszahlengenerator
-            let mut local_count = 0;           // Lokaler Zähler für diesen Chunk
-            let end = (start + chunk_size).min(tropfenzahl);
-            
-            // Monte-Carlo-Simulation für diesen Chunk
-            for _ in start..end {
-                // Generiere zufällige Punkte im Einheitsquadrat [0,1] × [0,1]
-                let x: f64 = rng.gen();  // Zufällige x-Koordinate
-                let y: f64 = rng.gen();  // Zufällige y-Koordinate
-                
-                // Prüfe, ob Punkt im Viertelkreis liegt (x² + y² ≤ 1)
-                if x * x + y * y <= 1.0 {
-                    local_count += 1;
-                }
+
+    if kern_cpusets.is_empty() {
+        eprintln!("NUMA-Pinning deaktiviert: keine CPU-Sets für erkannte Kerne verfügbar.");
+        return None;
+    }
+
+    let kern_anzahl = kerne.len().max(1);
+    let topologie_für_handler = Arc::clone(&topologie);
+
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(kern_anzahl)
+        .start_handler(move |worker_index| {
+            if let Some(cpuset) = kern_cpusets.get(worker_index % kern_cpusets.len()) {
+                let _ = topologie_für_handler.bind_cpu(cpuset, CpuBindingFlags::THREAD);
             }
-            
-            // Atomare Addition zum Gesamtzähler
-            counter.fetch_add(local_count, Ordering::Relaxed);
-        });
-
-    // Berechne Pi-Approximation
-    // π ≈ 4 * (Punkte im Kreis / Gesamtpunkte)
-    4.0 * (counter.load(Ordering::Relaxed) as f64) / (tropfenzahl as f64)
+        })
+        .build();
+
+    match pool {
+        Ok(pool) => Some(pool),
+        Err(e) => {
+            eprintln!("NUMA-Pinning deaktiviert: ThreadPool konnte nicht erstellt werden ({}).", e);
+            None
+        }
+    }
 }
 
 // Hilfsfunktion für Benutzereingabe
@@ -88,17 +141,104 @@ fn validate_tropfenzahl(input: &str) -> Result<u64, &'static str> {
     }
 }
 
+// Führt die Monte-Carlo-Simulation für jede Stufe in `stufen` aus und sammelt
+// die Ergebnisse, sodass man die 1/√N-Konvergenz über mehrere Größenordnungen
+// hinweg vergleichen kann.
+fn fuehre_sweep_aus(stufen: &[u64], modus: SamplingModus, seed: Option<u64>) -> Vec<Ergebnis> {
+    let threads = rayon::current_num_threads();
+
+    stufen
+        .iter()
+        .map(|&tropfenzahl| {
+            let start = Instant::now();
+            let pi_approx = approximiere_pi(tropfenzahl, modus, seed);
+            let dauer = start.elapsed();
+
+            let (std_fehler, konfidenz_intervall) = berechne_konfidenz(pi_approx, tropfenzahl, modus);
+
+            Ergebnis {
+                pi_approx,
+                dauer,
+                tropfenzahl,
+                threads,
+                std_fehler,
+                konfidenz_intervall,
+            }
+        })
+        .collect()
+}
+
+// Formatiert Std.-Fehler/Konfidenzintervall für die Tabellenausgabe, oder "N/A"
+// wenn sie nicht aussagekräftig sind (z.B. im deterministischen Halton-Modus).
+fn formatiere_konfidenz(ergebnis: &Ergebnis) -> (String, String) {
+    match (ergebnis.std_fehler, ergebnis.konfidenz_intervall) {
+        (Some(std_fehler), Some((unten, oben))) => (
+            format!("{:.10}", std_fehler),
+            format!("[{:.6}, {:.6}]", unten, oben),
+        ),
+        _ => ("N/A".to_string(), "N/A".to_string()),
+    }
+}
+
+// Gibt die Sweep-Ergebnisse als Tabelle auf stdout aus.
+fn drucke_sweep_tabelle(ergebnisse: &[Ergebnis]) {
+    let kopfzeile = format!(
+        "{:>12} | {:>14} | {:>14} | {:>14} | {:>24} | {:>12}",
+        "Tropfen", "π-Approximation", "Abweichung", "Std.-Fehler", "95%-Konfidenzintervall", "Laufzeit"
+    );
+    println!("\n{}", kopfzeile);
+    println!("{}", "-".repeat(kopfzeile.chars().count()));
+    for ergebnis in ergebnisse {
+        let (std_fehler, konfidenz_intervall) = formatiere_konfidenz(ergebnis);
+        println!(
+            "{:>12} | {:>14.10} | {:>14.10} | {:>14} | {:>24} | {:>12.2?}",
+            ergebnis.tropfenzahl,
+            ergebnis.pi_approx,
+            ergebnis.abweichung(),
+            std_fehler,
+            konfidenz_intervall,
+            ergebnis.dauer
+        );
+    }
+}
+
+// Schreibt die Sweep-Ergebnisse als CSV auf stdout (für Weiterverarbeitung, z.B. Plotten).
+fn drucke_sweep_csv(ergebnisse: &[Ergebnis]) {
+    println!("\ntropfenzahl,pi_approx,abweichung,std_fehler,ki_unten,ki_oben,dauer_sekunden,threads");
+    for ergebnis in ergebnisse {
+        let std_fehler = ergebnis
+            .std_fehler
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "N/A".to_string());
+        let (ki_unten, ki_oben) = ergebnis
+            .konfidenz_intervall
+            .map(|(unten, oben)| (unten.to_string(), oben.to_string()))
+            .unwrap_or_else(|| ("N/A".to_string(), "N/A".to_string()));
+        println!(
+            "{},{},{},{},{},{},{},{}",
+            ergebnis.tropfenzahl,
+            ergebnis.pi_approx,
+            ergebnis.abweichung(),
+            std_fehler,
+            ki_unten,
+            ki_oben,
+            ergebnis.dauer.as_secs_f64(),
+            ergebnis.threads
+        );
+    }
+}
+
 // Hauptfunktion zur Benutzerinteraktion und Berechnung
-fn berechne_pi() -> Result<Ergebnis, &'static str> {
+fn berechne_pi(modus: SamplingModus, seed: Option<u64>) -> Result<Ergebnis, &'static str> {
     // Benutzereingabe für Tropfenzahl
     let input = get_user_input("\nGib die Anzahl der Tropfen (Punkte) ein: ");
-    
+
     // Validierung mit Fallback auf Standardwert
     let tropfenzahl = validate_tropfenzahl(&input).unwrap_or_else(|_| {
         println!("Verwende Standardwert von {} Tropfen.", DEFAULT_TROPFEN);
         DEFAULT_TROPFEN
     });
-    
+
     // Bestätigung durch Benutzer
     if get_user_input("\nMöchtest du mit der Berechnung fortfahren? (Y/n): ")
         .to_lowercase()
@@ -109,8 +249,9 @@ fn berechne_pi() -> Result<Ergebnis, &'static str> {
     // Durchführung der Berechnung mit Zeitmessung
     let threads = rayon::current_num_threads();
     let start = Instant::now();
-    let pi_approx = approximiere_pi(tropfenzahl);
+    let pi_approx = approximiere_pi(tropfenzahl, modus, seed);
     let dauer = start.elapsed();
+    let (std_fehler, konfidenz_intervall) = berechne_konfidenz(pi_approx, tropfenzahl, modus);
 
     // Rückgabe der Ergebnisse
     Ok(Ergebnis {
@@ -118,6 +259,8 @@ fn berechne_pi() -> Result<Ergebnis, &'static str> {
         dauer,
         tropfenzahl,
         threads,
+        std_fehler,
+        konfidenz_intervall,
     })
 }
 
@@ -130,15 +273,90 @@ fn main() {
     println!("Der Quellcode kann hier gefunden werden: https://github.com/luigiistcrazy/pi_approximieren");
     println!("\nVerfügbare Threads: {}", rayon::current_num_threads());
 
+    // Moduswahl: einzelne Messung oder Konvergenz-Sweep über mehrere Stichprobengrößen.
+    let lauf_modus = get_user_input(
+        "\nModus wählen - (1) Einzelmessung, (2) Konvergenz-Sweep [1]: ",
+    );
+
+    // Sampling-Modus: Pseudozufall (Standard) oder deterministische Halton-Folge (QMC).
+    let sampling_modus = if get_user_input(
+        "Sampling wählen - Pseudozufall (p) oder Halton-Folge (h) [p]: ",
+    )
+    .to_lowercase()
+    .starts_with('h')
+    {
+        SamplingModus::Halton
+    } else {
+        SamplingModus::Pseudozufall
+    };
+
+    // NUMA-bewusstes Thread-Pinning: auf Mehrsockel-/Mehrkern-Maschinen stabilisiert
+    // es die "Punkte pro Sekunde"-Werte, weil Rayon-Worker nicht mehr zwischen
+    // Kernen migrieren.
+    let numa_pool = if get_user_input(
+        "NUMA-bewusstes Thread-Pinning aktivieren? (y/N): ",
+    )
+    .to_lowercase()
+    .starts_with('y')
+    {
+        erstelle_numa_threadpool()
+    } else {
+        None
+    };
+
+    // Optionaler Seed für reproduzierbare Läufe: derselbe Seed, dieselbe
+    // Tropfenzahl und dieselbe Thread-Zahl liefern bitgenau dasselbe π.
+    let seed_eingabe = get_user_input("Seed für reproduzierbare Läufe (leer = zufällig): ");
+    let seed: Option<u64> = seed_eingabe.parse().ok();
+
+    let berechnung = move || {
+    if lauf_modus.trim() == "2" {
+        println!(
+            "\nFühre Sweep über {} Stufen aus ({} bis {} Tropfen)...",
+            SWEEP_STUFEN.len(),
+            SWEEP_STUFEN.first().unwrap(),
+            SWEEP_STUFEN.last().unwrap()
+        );
+
+        let ergebnisse = fuehre_sweep_aus(&SWEEP_STUFEN, sampling_modus, seed);
+        drucke_sweep_tabelle(&ergebnisse);
+
+        if get_user_input("\nTabelle zusätzlich als CSV ausgeben? (y/N): ")
+            .to_lowercase()
+            .starts_with('y')
+        {
+            drucke_sweep_csv(&ergebnisse);
+        }
+
+        return;
+    }
+
     // Ausführung der Berechnung und Ausgabe der Ergebnisse
-    match berechne_pi() {
+    match berechne_pi(sampling_modus, seed) {
         Ok(ergebnis) => {
             println!("\nErgebnis:");
             println!("π ≈ {:.10}", ergebnis.pi_approx);
+            match (ergebnis.konfidenz_intervall, ergebnis.std_fehler) {
+                (Some((unten, oben)), Some(std_fehler)) => println!(
+                    "95%-Konfidenzintervall: [{:.10}, {:.10}] (Std.-Fehler: {:.10})",
+                    unten, oben, std_fehler
+                ),
+                _ => println!(
+                    "95%-Konfidenzintervall: N/A (nur für Sampling-Modus Pseudozufall aussagekräftig)"
+                ),
+            }
             println!("Verwendete Tropfen: {}", ergebnis.tropfenzahl);
             println!("Berechnungsdauer: {:.2?}", ergebnis.dauer);
             println!("Verwendete Threads: {}", ergebnis.threads);
         }
         Err(e) => println!("\n{}", e),
     }
+    };
+
+    // Führt die Berechnung im NUMA-gepinnten Pool aus, falls aktiviert, sonst im
+    // globalen Rayon-Pool.
+    match &numa_pool {
+        Some(pool) => pool.install(berechnung),
+        None => berechnung(),
+    }
 }
@@ -0,0 +1,312 @@
+//! Kernlogik der Monte-Carlo-π-Approximation als Bibliothek: Die eigentliche
+//! Berechnung (`approximiere_pi`, `PiRechner`) ist hier von der CLI getrennt,
+//! damit sie mit einem festen Seed reproduzierbar aufgerufen und unabhängig
+//! von einem Terminal getestet werden kann.
+
+use crossbeam_utils::CachePadded;
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+use rayon::prelude::*;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+// Minimale Chunk-Größe für parallele Verarbeitung.
+const CHUNK_SIZE: u64 = 10_000;
+
+/// Sampling-Modus für die Punkteerzeugung: Pseudozufall (O(1/√N)-Konvergenz)
+/// oder eine deterministische Halton-Folge (Quasi-Monte-Carlo, O((log N)²/N)).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SamplingModus {
+    Pseudozufall,
+    Halton,
+}
+
+/// Berechnet die radikale Inverse φ_b(i) in Basis `basis` für den globalen
+/// Punktindex `i`, damit die Halton-Folge über Chunks/Threads hinweg kohärent
+/// und nicht dupliziert ist.
+fn radikale_inverse(mut i: u64, basis: u64) -> f64 {
+    let mut ergebnis = 0.0;
+    let mut nenner = 1.0;
+    while i > 0 {
+        nenner *= basis as f64;
+        ergebnis += (i % basis) as f64 / nenner;
+        i /= basis;
+    }
+    ergebnis
+}
+
+// Mischt Seed und Chunk-Startindex SplitMix64-artig zu einem eigenen, aber
+// deterministischen RNG-Zustand pro Chunk, damit Seed + Tropfenzahl +
+// Thread-Zahl bitgenau dasselbe π liefern.
+fn chunk_rng(seed: u64, start: u64) -> SmallRng {
+    let mut z = seed.wrapping_add(start.wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^= z >> 31;
+    SmallRng::seed_from_u64(z)
+}
+
+/// Abstraktion über atomare Ganzzahlen, damit `ShardedCounter` einen einzigen
+/// generischen Code-Pfad für `AtomicU64` (freie Funktion `approximiere_pi`)
+/// und `AtomicUsize` (`PiRechner`) teilen kann, statt Shard-Verwaltung und
+/// Methodennamen pro Zahlentyp zu duplizieren.
+trait AtomicZaehler {
+    type Wert: Copy + std::iter::Sum;
+
+    fn neu() -> Self;
+    fn addiere(&self, wert: Self::Wert);
+    fn wert(&self) -> Self::Wert;
+}
+
+impl AtomicZaehler for AtomicU64 {
+    type Wert = u64;
+
+    fn neu() -> Self {
+        AtomicU64::new(0)
+    }
+
+    fn addiere(&self, wert: u64) {
+        self.fetch_add(wert, Ordering::Relaxed);
+    }
+
+    fn wert(&self) -> u64 {
+        self.load(Ordering::Relaxed)
+    }
+}
+
+impl AtomicZaehler for AtomicUsize {
+    type Wert = usize;
+
+    fn neu() -> Self {
+        AtomicUsize::new(0)
+    }
+
+    fn addiere(&self, wert: usize) {
+        self.fetch_add(wert, Ordering::Relaxed);
+    }
+
+    fn wert(&self) -> usize {
+        self.load(Ordering::Relaxed)
+    }
+}
+
+/// Gesharded Zähler: ein Zähler pro Rayon-Worker-Thread statt eines einzelnen
+/// globalen Zählers, damit bei vielen kleinen Chunks nicht alle Threads
+/// dieselbe Cache-Line beschreiben. `CachePadded` hält jedes Shard auf einer
+/// eigenen Cache-Line.
+struct ShardedCounter<T> {
+    shards: Vec<CachePadded<T>>,
+}
+
+impl<T: AtomicZaehler> ShardedCounter<T> {
+    fn neu(anzahl_shards: usize) -> Self {
+        ShardedCounter {
+            shards: (0..anzahl_shards.max(1))
+                .map(|_| CachePadded::new(T::neu()))
+                .collect(),
+        }
+    }
+
+    fn addiere(&self, wert: T::Wert) {
+        let index = rayon::current_thread_index().unwrap_or(0) % self.shards.len();
+        self.shards[index].addiere(wert);
+    }
+
+    fn summe(&self) -> T::Wert {
+        self.shards.iter().map(|shard| shard.wert()).sum()
+    }
+}
+
+/// Hauptfunktion zur Pi-Annäherung mit der Monte-Carlo-Methode:
+/// π ≈ 4 * (Punkte im Viertelkreis / Gesamtpunkte).
+///
+/// Mit `seed = Some(...)` ist das Ergebnis reproduzierbar: derselbe Seed,
+/// dieselbe Tropfenzahl und dieselbe Thread-Zahl liefern bitgenau dasselbe π.
+pub fn approximiere_pi(tropfenzahl: u64, modus: SamplingModus, seed: Option<u64>) -> f64 {
+    let threads = rayon::current_num_threads();
+    let counter = Arc::new(ShardedCounter::<AtomicU64>::neu(threads));
+
+    // Berechne optimale Chunk-Größe für Load Balancing:
+    // - Mindestens CHUNK_SIZE (10.000)
+    // - Maximal tropfenzahl
+    // - Ziel: ca. 10 Chunks pro Thread
+    let chunk_size = (tropfenzahl / (threads as u64 * 10))
+        .max(CHUNK_SIZE)
+        .min(tropfenzahl);
+
+    // Erstelle Vektor mit Start-Indizes für jeden Chunk.
+    let chunks: Vec<u64> = (0..tropfenzahl).step_by(chunk_size as usize).collect();
+
+    chunks.par_iter().for_each(|&start| {
+        // Jeder Chunk bekommt seinen eigenen RNG: deterministisch aus Seed +
+        // Start abgeleitet, oder aus Entropie, falls kein Seed vorgegeben ist.
+        let mut rng = match seed {
+            Some(seed) => chunk_rng(seed, start),
+            None => SmallRng::from_entropy(),
+        };
+        let mut local_count = 0u64;
+        let end = (start + chunk_size).min(tropfenzahl);
+
+        for i in start..end {
+            // `i` ist der globale Punktindex - wichtig für die Halton-Folge, damit
+            // sie über alle Chunks/Threads hinweg kohärent und nicht dupliziert ist.
+            let (x, y): (f64, f64) = match modus {
+                SamplingModus::Pseudozufall => (rng.gen(), rng.gen()),
+                SamplingModus::Halton => (radikale_inverse(i, 2), radikale_inverse(i, 3)),
+            };
+
+            if x * x + y * y <= 1.0 {
+                local_count += 1;
+            }
+        }
+
+        counter.addiere(local_count);
+    });
+
+    4.0 * (counter.summe() as f64) / (tropfenzahl as f64)
+}
+
+/// Enthält den individuellen Fortschritt eines Threads.
+struct ThreadFortschritt {
+    fortschritt: AtomicUsize,
+}
+
+/// Spinner-Anzeige, die sich kontinuierlich ändert.
+struct Spinner {
+    zustände: Vec<&'static str>,
+    aktuell: AtomicUsize,
+}
+
+impl Spinner {
+    fn new() -> Self {
+        Spinner {
+            zustände: vec!["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"],
+            aktuell: AtomicUsize::new(0),
+        }
+    }
+
+    fn nächster(&self) -> &'static str {
+        let aktuell = self.aktuell.fetch_add(1, Ordering::Relaxed) % self.zustände.len();
+        self.zustände[aktuell]
+    }
+}
+
+/// Die zentrale Struktur für die Monte-Carlo-Simulation mit Fortschrittsanzeige.
+///
+/// Mit `seed = Some(...)` ist `verarbeite_batch` reproduzierbar: derselbe Seed,
+/// dieselbe Tropfenzahl und dieselbe Thread-Zahl liefern bitgenau dasselbe π.
+pub struct PiRechner {
+    punkte_innen: ShardedCounter<AtomicUsize>,
+    punkte_gesamt: ShardedCounter<AtomicUsize>,
+    thread_fortschritte: Vec<ThreadFortschritt>,
+    spinner: Spinner,
+    sampling_modus: SamplingModus,
+    seed: Option<u64>,
+}
+
+impl PiRechner {
+    /// Konstruktor: Erstellt eine neue Instanz und initialisiert atomare Variablen.
+    pub fn new(thread_anzahl: usize, sampling_modus: SamplingModus, seed: Option<u64>) -> Self {
+        let thread_fortschritte = (0..thread_anzahl)
+            .map(|_| ThreadFortschritt {
+                fortschritt: AtomicUsize::new(0),
+            })
+            .collect();
+
+        PiRechner {
+            punkte_innen: ShardedCounter::neu(thread_anzahl),
+            punkte_gesamt: ShardedCounter::neu(thread_anzahl),
+            thread_fortschritte,
+            spinner: Spinner::new(),
+            sampling_modus,
+            seed,
+        }
+    }
+
+    /// Berechnet die Annäherung an π mit der Formel: π ≈ 4 * (Punkte_innen / Punkte_gesamt).
+    pub fn berechne_pi(&self) -> f64 {
+        let innen = self.punkte_innen.summe() as f64;
+        let gesamt = self.punkte_gesamt.summe() as f64;
+        4.0 * innen / gesamt
+    }
+
+    /// Verarbeitet einen Batch von Punkten und prüft, ob sie im Einheitskreis liegen.
+    /// `start` ist der globale Punktindex des ersten Punkts in diesem Batch - im
+    /// Halton- und im geseedeten Pseudozufall-Modus muss er chunk-übergreifend
+    /// eindeutig sein.
+    pub fn verarbeite_batch(&self, thread_id: usize, start: usize, batch_größe: usize) {
+        let mut rng = match self.seed {
+            Some(seed) => chunk_rng(seed, start as u64),
+            None => SmallRng::from_entropy(),
+        };
+        let mut lokale_treffer = 0;
+
+        for offset in 0..batch_größe {
+            let (x, y): (f64, f64) = match self.sampling_modus {
+                SamplingModus::Pseudozufall => (rng.gen(), rng.gen()),
+                SamplingModus::Halton => (
+                    radikale_inverse((start + offset) as u64, 2),
+                    radikale_inverse((start + offset) as u64, 3),
+                ),
+            };
+
+            if x * x + y * y <= 1.0 {
+                lokale_treffer += 1;
+            }
+        }
+
+        self.punkte_innen.addiere(lokale_treffer);
+        self.punkte_gesamt.addiere(batch_größe);
+
+        let aktuell = self.thread_fortschritte[thread_id]
+            .fortschritt
+            .load(Ordering::Relaxed)
+            + batch_größe;
+        self.thread_fortschritte[thread_id]
+            .fortschritt
+            .store(aktuell, Ordering::Relaxed);
+    }
+
+    /// Fortschritt (verarbeitete Punkte) des Threads mit Index `thread_id`.
+    pub fn thread_fortschritt(&self, thread_id: usize) -> usize {
+        self.thread_fortschritte[thread_id]
+            .fortschritt
+            .load(Ordering::Relaxed)
+    }
+
+    /// Gesamtanzahl bisher verarbeiteter Punkte über alle Threads hinweg.
+    pub fn gesamt_verarbeitet(&self) -> usize {
+        self.punkte_gesamt.summe()
+    }
+
+    /// Nächster Zustand der Spinner-Anzeige.
+    pub fn naechster_spinner_zustand(&self) -> &'static str {
+        self.spinner.nächster()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Derselbe Seed, dieselbe Tropfenzahl und dieselbe Thread-Zahl müssen
+    // bitgenau dasselbe π liefern - das ist der ganze Zweck des Seed-Parameters.
+    #[test]
+    fn approximiere_pi_ist_mit_seed_reproduzierbar() {
+        let erster_lauf = approximiere_pi(1_000_000, SamplingModus::Pseudozufall, Some(42));
+        let zweiter_lauf = approximiere_pi(1_000_000, SamplingModus::Pseudozufall, Some(42));
+
+        assert_eq!(erster_lauf, zweiter_lauf);
+    }
+
+    // Die Halton-Folge ist per Konstruktion deterministisch, auch ohne Seed,
+    // und sollte für N=1e6 deutlich näher an π liegen als reiner Zufall.
+    #[test]
+    fn halton_sampling_ist_deterministisch_und_konvergiert() {
+        let erster_lauf = approximiere_pi(1_000_000, SamplingModus::Halton, None);
+        let zweiter_lauf = approximiere_pi(1_000_000, SamplingModus::Halton, None);
+
+        assert_eq!(erster_lauf, zweiter_lauf);
+        assert!((erster_lauf - std::f64::consts::PI).abs() < 1e-4);
+    }
+}
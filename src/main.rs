@@ -1,102 +1,9 @@
+use pi_approximieren::{PiRechner, SamplingModus}; // Kernlogik der Monte-Carlo-Simulation.
 use rayon::prelude::*;                          // Parallelisierungsbibliothek Rayon für Multithreading.
 use std::io::{self, Write};                     // Eingabe-/Ausgabefunktionen mit Flush zum Schreiben in den Output-Buffer.
-use std::sync::atomic::{AtomicUsize, Ordering}; // Atomare Variablen für thread-sichere Operationen.
 use std::sync::Arc;                             // Atomics sind einfach teilbar zwischen Threads.
 use std::time::{Duration, Instant};             // Für Zeitmessung wie für Effizienzberechnung.
 
-/// Spinner-Anzeige, die sich kontinuierlich ändert.
-struct Spinner {
-    zustände: Vec<&'static str>, // Verschiedene Zustände des Spinners.
-    aktuell: AtomicUsize,        // Aktueller Zustand des Spinners (atomar für Thread-Sicherheit).
-}
-
-impl Spinner {
-    // Initialisiert einen neuen Spinner mit vorgegebenen Zuständen.
-    fn new() -> Self {
-        Spinner {
-            zustände: vec!["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"],
-            aktuell: AtomicUsize::new(0), // Startet beim ersten Zustand.
-        }
-    }
-
-    // Gibt den nächsten Spinner-Zustand zurück, indem wir den Index inkrementieren und mod `länge` nehmen.
-    fn nächster(&self) -> &'static str {
-        let aktuell = self.aktuell.fetch_add(1, Ordering::Relaxed) % self.zustände.len();
-        self.zustände[aktuell]
-    }
-}
-
-/// Enthält den individuellen Fortschritt eines Threads.
-struct ThreadFortschritt {
-    fortschritt: AtomicUsize, // Atomare Variable für Punktanzahl, die ein Thread verarbeitet hat.
-}
-
-/// Die zentrale Struktur für die Monte-Carlo-Simulation.
-struct PiRechner {
-    punkte_innen: AtomicUsize,                   // Punkte, die innerhalb des Einheitskreises liegen.
-    punkte_gesamt: AtomicUsize,                  // Gesamtanzahl an Punkten (innerhalb und außerhalb des Kreises).
-    thread_fortschritte: Vec<ThreadFortschritt>, // Pro-Thread Fortschritt.
-    spinner: Spinner,                            // Spinner.
-}
-
-impl PiRechner {
-    /// Konstruktor: Erstellt eine neue Instanz und initialisiert atomare Variablen.
-    fn new(thread_anzahl: usize) -> Self {
-        let thread_fortschritte = (0..thread_anzahl)
-            .map(|_| ThreadFortschritt {
-                fortschritt: AtomicUsize::new(0),
-            })
-            .collect();
-
-        PiRechner {
-            punkte_innen: AtomicUsize::new(0), // Am Anfang sind keine Punkte gezählt.
-            punkte_gesamt: AtomicUsize::new(0),
-            thread_fortschritte,
-            spinner: Spinner::new(),
-        }
-    }
-
-    /// Berechnet die Annäherung an π mit der Formel: π ≈ 4 * (Punkte_innen / Punkte_gesamt).
-    fn berechne_pi(&self) -> f64 {
-        let innen = self.punkte_innen.load(Ordering::Relaxed) as f64;
-        let gesamt = self.punkte_gesamt.load(Ordering::Relaxed) as f64;
-        4.0 * innen / gesamt
-    }
-
-    /// Verarbeitet einen Batch von Zufallspunkten und prüft, ob sie im Einheitskreis liegen.
-    fn verarbeite_batch(&self, thread_id: usize, batch_größe: usize) {
-        use rand::Rng;
-        let mut rng = rand::thread_rng(); // Zufallszahlen-Generator.
-        let mut lokale_treffer = 0;       // Zähler für Punkte innerhalb des Kreises.
-
-        // Generiere `batch_größe` Punkte und prüfe, ob sie im Kreis liegen.
-        for _ in 0..batch_größe {
-            let x: f64 = rng.gen(); // Zufällige x-Koordinate zwischen 0 und 1.
-            let y: f64 = rng.gen(); // Zufällige y-Koordinate zwischen 0 und 1.
-
-            // Prüfe, ob (x, y) innerhalb des Kreises liegt:
-            // Ein Punkt liegt innerhalb, wenn: x² + y² ≤ 1
-            if x * x + y * y <= 1.0 {
-                lokale_treffer += 1; // Zähle Treffer innerhalb des Kreises.
-            }
-        }
-
-        // Atomar lokales Ergebnis zur globalen Trefferanzahl hinzufügen.
-        self.punkte_innen
-            .fetch_add(lokale_treffer, Ordering::Relaxed);
-        self.punkte_gesamt.fetch_add(batch_größe, Ordering::Relaxed);
-
-        // Aktualisiere den Fortschritt dieses Threads mit der Anzahl der verarbeiteten Punkte.
-        let aktuell = self.thread_fortschritte[thread_id]
-            .fortschritt
-            .load(Ordering::Relaxed)
-            + batch_größe;
-        self.thread_fortschritte[thread_id]
-            .fortschritt
-            .store(aktuell, Ordering::Relaxed); // Fortschritt-Update für Balkenanpassung.
-    }
-}
-
 /// Erstellt einen Balken.
 fn erstelle_fortschrittsbalken(prozent: f64, breite: usize) -> String {
     // Breite des Balkens, die gefüllt sein sollte, proportional zu `prozent`.
@@ -129,15 +36,13 @@ fn aktualisiere_anzeige(
     thread_anzahl: usize,
     punkte_pro_thread: usize,
 ) -> io::Result<()> {
-    print!("\x1B[H");                                         // ANSI-Escape-Code, um den Cursor zu bewegen.
-    println!("Berechne... {}\n", rechner.spinner.nächster()); // Spinner Zustand.
+    print!("\x1B[H");                                                      // ANSI-Escape-Code, um den Cursor zu bewegen.
+    println!("Berechne... {}\n", rechner.naechster_spinner_zustand());    // Spinner Zustand.
     println!("Threads:\n");
 
     // Aktualisiere für jeden Thread den Fortschritt.
     for i in 0..thread_anzahl {
-        let fortschritt = rechner.thread_fortschritte[i]
-            .fortschritt
-            .load(Ordering::Relaxed);
+        let fortschritt = rechner.thread_fortschritt(i);
         let prozent = (fortschritt as f64 / punkte_pro_thread as f64) * 100.0;
         let balken = erstelle_fortschrittsbalken(prozent.min(100.0), 50); // Fortschritt als Balken.
         println!("[Thread {}]: {}", i, balken);
@@ -189,8 +94,25 @@ fn main() -> io::Result<()> {
         return Ok(()); // Das Programm wird beendet.
     }
 
+    // Sampling-Modus: Pseudozufall (Standard) oder deterministische Halton-Folge (QMC).
+    let sampling_modus = if get_user_input(
+        "Sampling wählen - Pseudozufall (p) oder Halton-Folge (h) [p]: ",
+    )
+    .to_lowercase()
+    .starts_with('h')
+    {
+        SamplingModus::Halton
+    } else {
+        SamplingModus::Pseudozufall
+    };
+
+    // Optionaler Seed für reproduzierbare Läufe: derselbe Seed, dieselbe
+    // Tropfenzahl und dieselbe Thread-Zahl liefern bitgenau dasselbe π.
+    let seed_eingabe = get_user_input("Seed für reproduzierbare Läufe (leer = zufällig): ");
+    let seed: Option<u64> = seed_eingabe.parse().ok();
+
     // Erstellt eine neue geteilte (shared) Instanz des PiRechners, um thread-sicher Punkte zu berechnen.
-    let rechner = Arc::new(PiRechner::new(thread_anzahl));
+    let rechner = Arc::new(PiRechner::new(thread_anzahl, sampling_modus, seed));
 
     // Startet eine Zeitmessung, um die gesamte Berechnungsdauer zu erfassen.
     let start_zeit = Instant::now();
@@ -217,8 +139,10 @@ fn main() -> io::Result<()> {
                 let verbleibend = punkte_pro_thread - verarbeitet;
                 let batch_größe = 10_000.min(verbleibend); // Maximale Batch-Größe ist 10.000 Punkte.
 
-                // Ruft die Batch-Verarbeitung des Monte-Carlo-Algorithmus auf.
-                rechner_klon.verarbeite_batch(thread_id, batch_größe);
+                // Ruft die Batch-Verarbeitung des Monte-Carlo-Algorithmus auf. Der globale
+                // Startindex ergibt sich aus dem festen Punktebereich dieses Threads.
+                let start = thread_id * punkte_pro_thread + verarbeitet;
+                rechner_klon.verarbeite_batch(thread_id, start, batch_größe);
 
                 // Erhöht den Zähler für verarbeitete Punkte.
                 verarbeitet += batch_größe;
@@ -230,7 +154,7 @@ fn main() -> io::Result<()> {
     let rechner_klon = Arc::clone(&rechner);
     std::thread::spawn(move || {
         // Solange die Gesamtanzahl an Punkten noch nicht erreicht ist:
-        while rechner_klon.punkte_gesamt.load(Ordering::SeqCst) < gesamt_punkte {
+        while rechner_klon.gesamt_verarbeitet() < gesamt_punkte {
             // Aktualisiere die Fortschrittsanzeige.
             if let Err(e) = aktualisiere_anzeige(&rechner_klon, thread_anzahl, punkte_pro_thread) {
                 // Gib eine Fehlermeldung aus, falls ein Fehler beim Aktualisieren der Anzeige auftritt.
@@ -262,7 +186,7 @@ fn main() -> io::Result<()> {
     let pi_approximation = rechner.berechne_pi();
 
     // Lädt die Gesamtanzahl der verarbeiteten Punkte, um dies in den Ergebnissen anzuzeigen.
-    let gesamt_punkte = rechner.punkte_gesamt.load(Ordering::Relaxed);
+    let gesamt_punkte = rechner.gesamt_verarbeitet();
 
     // Gibt die berechneten Ergebnisse an die Konsole aus:
     println!("\nErgebnisse:");